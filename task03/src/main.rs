@@ -5,6 +5,8 @@ use serde::Deserialize;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::Instruction,
     pubkey::Pubkey,
     signature::{Keypair, Signer},
     system_instruction,
@@ -20,6 +22,8 @@ use yellowstone_grpc_proto::{
 };
 
 const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+const CONFIRM_ATTEMPTS: u32 = 30;
+const CONFIRM_POLL_DELAY: Duration = Duration::from_millis(500);
 
 #[derive(Debug, Deserialize)]
 struct Config {
@@ -28,6 +32,10 @@ struct Config {
     amount_sol: f64,
     grpc_endpoint: String,
     grpc_api_key: String,
+    #[serde(default)]
+    compute_unit_limit: Option<u32>,
+    #[serde(default)]
+    compute_unit_price: Option<u64>,
 }
 
 async fn create_keypair(private_key: &str) -> Result<Keypair> {
@@ -50,17 +58,36 @@ async fn create_keypair(private_key: &str) -> Result<Keypair> {
     Err(anyhow::anyhow!("Invalid private key format. Expected base58 string, JSON array, or hex string"))
 }
 
+/// Builds the compute-budget instructions requested via `compute_unit_limit`
+/// and `compute_unit_price`, if any, to prepend ahead of the transfer so it's
+/// more likely to land when racing other transactions for the next slot.
+fn compute_budget_instructions(
+    compute_unit_limit: Option<u32>,
+    compute_unit_price: Option<u64>,
+) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    if let Some(limit) = compute_unit_limit {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(limit));
+    }
+    if let Some(price) = compute_unit_price {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+    }
+    instructions
+}
+
 async fn send_sol(
     rpc_client: Arc<RpcClient>,
     sender_key: &str,
     recipient: &str,
     amount_lamports: u64,
+    compute_unit_limit: Option<u32>,
+    compute_unit_price: Option<u64>,
 ) -> Result<String> {
     let keypair = create_keypair(sender_key).await?;
     let recipient_pubkey = Pubkey::from_str(recipient)
         .with_context(|| format!("Failed to parse recipient address: {}", recipient))?;
 
-    info!("Sending {} SOL from {} to {}", 
+    info!("Sending {} SOL from {} to {}",
         amount_lamports as f64 / LAMPORTS_PER_SOL as f64,
         keypair.pubkey(),
         recipient_pubkey
@@ -70,14 +97,15 @@ async fn send_sol(
         .get_latest_blockhash()
         .context("Failed to get recent blockhash")?;
 
-    let transfer_instruction = system_instruction::transfer(
+    let mut instructions = compute_budget_instructions(compute_unit_limit, compute_unit_price);
+    instructions.push(system_instruction::transfer(
         &keypair.pubkey(),
         &recipient_pubkey,
         amount_lamports,
-    );
+    ));
 
     let mut transaction = Transaction::new_with_payer(
-        &[transfer_instruction],
+        &instructions,
         Some(&keypair.pubkey()),
     );
     transaction.sign(&[&keypair], recent_blockhash);
@@ -86,9 +114,9 @@ async fn send_sol(
     let balance = rpc_client
         .get_balance(&keypair.pubkey())
         .context("Failed to get sender's balance")?;
-    
+
     info!("Sender's balance: {} SOL", balance as f64 / LAMPORTS_PER_SOL as f64);
-    
+
     if balance < amount_lamports {
         return Err(anyhow::anyhow!(
             "Insufficient balance. Required: {} SOL, Available: {} SOL",
@@ -97,13 +125,47 @@ async fn send_sol(
         ));
     }
 
+    // Fire-and-forget: don't block the block handler on confirmation, since
+    // it's racing to land in the very next slot.
     let signature = rpc_client
-        .send_and_confirm_transaction_with_spinner(&transaction)
+        .send_transaction(&transaction)
         .context("Failed to send transaction")?;
 
     Ok(signature.to_string())
 }
 
+/// Polls for confirmation of a signature returned by the non-blocking
+/// `send_transaction` path, logging the outcome once it lands (or times out).
+async fn confirm_signature_async(rpc_client: Arc<RpcClient>, signature: String) {
+    let parsed_signature = match solana_sdk::signature::Signature::from_str(&signature) {
+        Ok(sig) => sig,
+        Err(e) => {
+            error!("Failed to parse signature {} for confirmation: {}", signature, e);
+            return;
+        }
+    };
+
+    for _ in 0..CONFIRM_ATTEMPTS {
+        match rpc_client.get_signature_status(&parsed_signature) {
+            Ok(Some(Ok(()))) => {
+                info!("Transaction {} confirmed", signature);
+                return;
+            }
+            Ok(Some(Err(e))) => {
+                error!("Transaction {} failed: {}", signature, e);
+                return;
+            }
+            Ok(None) => tokio::time::sleep(CONFIRM_POLL_DELAY).await,
+            Err(e) => {
+                error!("Failed to poll status for {}: {}", signature, e);
+                return;
+            }
+        }
+    }
+
+    error!("Timed out waiting for confirmation of {}", signature);
+}
+
 async fn subscribe_to_blocks(
     grpc_endpoint: String,
     api_key: String,
@@ -111,6 +173,8 @@ async fn subscribe_to_blocks(
     sender_key: String,
     recipient: String,
     amount_sol: f64,
+    compute_unit_limit: Option<u32>,
+    compute_unit_price: Option<u64>,
 ) -> Result<()> {
     let mut client = GeyserGrpcClient::build_from_shared(grpc_endpoint)?
         .x_token(Some(api_key))?
@@ -163,15 +227,20 @@ async fn subscribe_to_blocks(
                         bs58::encode(&update.blockhash).into_string()
                     );
                     
-                    // Send SOL transaction
+                    // Send SOL transaction without blocking the block handler on confirmation
                     let amount_lamports = (amount_sol * LAMPORTS_PER_SOL as f64) as u64;
                     match send_sol(
                         rpc_client.clone(),
                         &sender_key,
                         &recipient,
                         amount_lamports,
+                        compute_unit_limit,
+                        compute_unit_price,
                     ).await {
-                        Ok(signature) => info!("Transaction sent successfully. Signature: {}", signature),
+                        Ok(signature) => {
+                            info!("Transaction sent. Signature: {}", signature);
+                            tokio::spawn(confirm_signature_async(rpc_client.clone(), signature));
+                        }
                         Err(e) => error!("Failed to send transaction: {}", e),
                     }
                 }
@@ -224,6 +293,8 @@ async fn main() -> Result<()> {
         config.sender_key,
         config.recipient,
         config.amount_sol,
+        config.compute_unit_limit,
+        config.compute_unit_price,
     ).await?;
 
     Ok(())